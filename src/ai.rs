@@ -0,0 +1,206 @@
+//! A computer opponent for [`crate::Player::Two`].
+//!
+//! [`best_move`] runs alpha-beta minimax to a fixed depth. Dots and boxes has an unusual rule
+//! that breaks the usual alternating-turn assumption: completing a box grants the same player
+//! another move, so a single turn can chain through many edges. The search below accounts for
+//! that by only flipping the mover when a claim completes zero squares.
+//!
+//! Full-depth search is only tractable once few edges remain, so when there are more untaken
+//! edges than `depth` plies to spend, we fall back to [`heuristic_move`] instead: take any free
+//! box (except that when only the last two boxes of a chain are left, the classic "double-cross"
+//! handback declines them to keep control of the endgame), otherwise play a move that doesn't
+//! hand the opponent one, and if every move hands something away, pick the move that leaves the
+//! fewest squares three-sided.
+
+use crate::{GameState, Player};
+
+/// Pick the edge `state.current_player` should claim next.
+///
+/// Searches up to `depth` plies with alpha-beta minimax when that's enough to reach a terminal
+/// state exactly; otherwise falls back to [`heuristic_move`].
+pub fn best_move(state: &GameState, depth: u32) -> usize {
+    let untaken = state.untaken_edges();
+    assert!(!untaken.is_empty(), "best_move called on a finished game");
+
+    if (untaken.len() as u32) <= depth {
+        minimax_move(state, &untaken, depth)
+    } else {
+        heuristic_move(state, &untaken)
+    }
+}
+
+/// Exact alpha-beta search: evaluate every untaken edge and return the one with the best score
+/// for `state.current_player`.
+fn minimax_move(state: &GameState, untaken: &[usize], depth: u32) -> usize {
+    let root = state.current_player;
+    let mut best_idx = untaken[0];
+    let mut best_score = i32::MIN;
+    let mut alpha = i32::MIN;
+    let beta = i32::MAX;
+
+    for &idx in untaken {
+        let mut next = state.clone();
+        let completed = next
+            .claim_edge(idx, root)
+            .expect("untaken_edges only returns edges that are still free");
+        let next_mover = if completed > 0 { root } else { root.other() };
+
+        let score = minimax(&next, next_mover, root, depth - 1, alpha, beta);
+        if score > best_score {
+            best_score = score;
+            best_idx = idx;
+        }
+        alpha = alpha.max(best_score);
+    }
+
+    best_idx
+}
+
+/// Alpha-beta minimax over `state`, scored from `root`'s perspective. `mover` is whoever plays
+/// next; the search maximizes when `mover == root` and minimizes otherwise, recursing with the
+/// *same* mover again whenever a claim completes a square (since that doesn't pass the turn).
+fn minimax(
+    state: &GameState,
+    mover: Player,
+    root: Player,
+    depth: u32,
+    mut alpha: i32,
+    mut beta: i32,
+) -> i32 {
+    if depth == 0 || state.is_finished() {
+        return evaluate(state, root);
+    }
+
+    let maximizing = mover == root;
+    let mut best = if maximizing { i32::MIN } else { i32::MAX };
+
+    for idx in state.untaken_edges() {
+        let mut next = state.clone();
+        let completed = next
+            .claim_edge(idx, mover)
+            .expect("untaken_edges only returns edges that are still free");
+        let next_mover = if completed > 0 { mover } else { mover.other() };
+
+        let score = minimax(&next, next_mover, root, depth - 1, alpha, beta);
+
+        if maximizing {
+            best = best.max(score);
+            alpha = alpha.max(best);
+        } else {
+            best = best.min(score);
+            beta = beta.min(best);
+        }
+
+        if beta <= alpha {
+            break;
+        }
+    }
+
+    best
+}
+
+/// Leaf evaluation: how many more boxes `root` has than their opponent.
+fn evaluate(state: &GameState, root: Player) -> i32 {
+    state.box_count(root) as i32 - state.box_count(root.other()) as i32
+}
+
+/// A one-ply heuristic used when the board is too large to search to the end: take free boxes
+/// (applying the double-cross handback whenever the only capture is the second-to-last box of a
+/// chain), otherwise avoid giving any away, and when that's impossible, play the move that
+/// leaves the fewest squares three-sided.
+fn heuristic_move(state: &GameState, untaken: &[usize]) -> usize {
+    let captures: Vec<usize> = untaken
+        .iter()
+        .copied()
+        .filter(|&idx| state.completes_a_square(idx))
+        .collect();
+
+    if !captures.is_empty() {
+        if let Some(handback) = double_cross_handback(state, &captures) {
+            return handback;
+        }
+
+        // Otherwise greedily take the capture that finishes the most squares at once.
+        return *captures
+            .iter()
+            .max_by_key(|&&idx| squares_completed_by(state, idx))
+            .unwrap();
+    }
+
+    // No free boxes available. Prefer a move that doesn't leave any square on three edges.
+    if let Some(&safe) = untaken.iter().find(|&&idx| is_safe(state, idx)) {
+        return safe;
+    }
+
+    // Every remaining move opens a chain for the opponent; play the one that leaves the fewest
+    // squares three-sided. The length of the chain behind those squares is not measured.
+    *untaken
+        .iter()
+        .min_by_key(|&&idx| squares_opened_by(state, idx))
+        .unwrap()
+}
+
+/// How many not-yet-complete squares would be completed by claiming `idx`.
+fn squares_completed_by(state: &GameState, idx: usize) -> usize {
+    let mut next = state.clone();
+    next.claim_edge(idx, state.current_player)
+        .expect("idx must be untaken")
+}
+
+/// How many squares would become three-sided (capturable by the opponent next) by claiming
+/// `idx`, without themselves being completed by it.
+fn squares_opened_by(state: &GameState, idx: usize) -> usize {
+    let mut next = state.clone();
+    next.claim_edge(idx, state.current_player)
+        .expect("idx must be untaken");
+
+    next.squares
+        .iter()
+        .filter(|square| square.taken_by.is_none() && next.square_edges_taken(square) == 3)
+        .count()
+}
+
+/// Whether claiming `idx` leaves every bordering square with two or fewer edges taken, i.e. it
+/// doesn't hand the opponent a free box on their next turn.
+fn is_safe(state: &GameState, idx: usize) -> bool {
+    squares_opened_by(state, idx) == 0
+}
+
+/// If `captures` holds exactly one capturing move, and claiming it would leave a single other
+/// square needing just one more edge to complete (i.e. the chain is down to its last two boxes),
+/// decline the capture: play that other square's remaining free edge instead (the
+/// "hard-hearted handback"). This leaves both boxes three-sided without completing either, so
+/// the opponent is forced to take both in one move and, since completing a box grants another
+/// turn, forced to open the next chain themselves.
+///
+/// Returns [None] when there's nothing to decline (no single capture, no chain to hand back, or
+/// the chain actually continues beyond these two boxes), in which case the caller just takes the
+/// capture.
+fn double_cross_handback(state: &GameState, captures: &[usize]) -> Option<usize> {
+    let &[only_capture] = captures else {
+        return None;
+    };
+
+    let (next_idx, next_square) = state.squares.iter().enumerate().find(|(_, square)| {
+        square.taken_by.is_none()
+            && square.edges.contains(&only_capture)
+            && state.square_edges_taken(square) == 2
+    })?;
+
+    let domino_edge = *next_square
+        .edges
+        .iter()
+        .find(|&&idx| idx != only_capture && state.edge(idx).taken_by.is_none())?;
+
+    // The chain really does end here only if `domino_edge` doesn't border a third still-open
+    // square; otherwise there's more chain left beyond this domino and we should keep capturing.
+    let chain_continues = state.squares.iter().enumerate().any(|(idx, square)| {
+        idx != next_idx && square.taken_by.is_none() && square.edges.contains(&domino_edge)
+    });
+
+    if chain_continues {
+        return None;
+    }
+
+    Some(domino_edge)
+}
@@ -1,31 +1,178 @@
+mod ai;
+mod menace;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
 use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use menace::{BoardKey, Learner, Outcome, Trainer};
+
+/// Where [`GameState::save`]/[`GameState::load`] read and write by default.
+const SAVE_PATH: &str = "save.json5";
+
+/// Where the learner opponent's matchbox memory is persisted between runs, so it keeps
+/// improving instead of starting fresh every time.
+const LEARNER_SAVE_PATH: &str = "learner.txt";
+
+/// Grid size used when no dimensions are given on the command line.
+const DEFAULT_GRID_ROWS: usize = 5;
+const DEFAULT_GRID_COLS: usize = 5;
+
+/// Smallest and largest board this supports.
+const MIN_GRID_DIMENSION: usize = 2;
+const MAX_GRID_DIMENSION: usize = 15;
+
+/// Fixed pixel size of each square. The window is laid out dynamically around the board (see
+/// [`GameState::window_size`]) rather than the board being rescaled to fit a fixed window.
+const SQUARE_SIZE: f32 = 48.0;
+
+/// Empty space left around the grid on every side, in pixels.
+const GRID_MARGIN: f32 = 20.0;
+
+/// Wall width as a fraction of the square size, so walls stay proportionally visible however
+/// large the board gets.
+const WALL_WIDTH_RATIO: f32 = 0.2;
 
-const WINDOW_WIDTH: u32 = 400;
-const WINDOW_HEIGHT: u32 = 400;
+/// How far (in pixels) outside an edge's bounding box a click still counts as a hit.
+/// Walls are thin, so without some slack they'd be annoyingly hard to click precisely.
+const EDGE_CLICK_TOLERANCE: f32 = 6.0;
 
-const GRID_ROWS: usize = 5;
-const GRID_COLS: usize = 5;
+/// How many plies the AI searches ahead before giving up on an exact minimax result and
+/// falling back to the heuristic. Raise this to make the computer opponent stronger.
+const AI_SEARCH_DEPTH: u32 = 6;
 
-const SQUARE_SIZE: f32 = 50.0;
-const WALL_WIDTH: f32 = 10.0;
+/// Font size used for the score/turn HUD and the end-of-game banner.
+const HUD_FONT_SIZE: u32 = 16;
+
+/// Which strategy plays [`Player::Two`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opponent {
+    /// Minimax search, via [`ai::best_move`].
+    Minimax,
+    /// The MENACE-style learner, via [`menace::Learner`]. Its matchbox memory is loaded from
+    /// and saved back to [`LEARNER_SAVE_PATH`], so it keeps getting stronger across runs.
+    Learner,
+}
+
+/// Command-line configuration for a run: the board size, an optional saved game to resume, and
+/// which opponent to play against.
+#[derive(Clone)]
+struct Args {
+    rows: usize,
+    cols: usize,
+    load_path: Option<String>,
+    opponent: Opponent,
+    /// When set (`--train <games>`), bootstrap the learner's memory via self-play and exit
+    /// instead of opening a window.
+    train_games: Option<u32>,
+}
 
 fn main() {
-    // Call the `model` function to create the initial `GameState`
+    let args = parse_args();
+
+    if let Some(games) = args.train_games {
+        train(games);
+        return;
+    }
+
+    // Call the `model` function to create the initial `Model`
     // this uses "Nannou" which is a creative-coding framework for Rust
     // - Website: https://nannou.cc/
     // - Docs: https://docs.rs/nannou/latest/nannou/
-    nannou::app(model).run();
+    nannou::app(move |app| model(app, args.clone())).run();
+}
+
+/// Bootstrap the learner opponent by playing it against the minimax AI for `games` games via
+/// [`Trainer::self_play`], saving the resulting matchbox memory to [`LEARNER_SAVE_PATH`] so
+/// `--learner` starts from something better than a blank slate.
+fn train(games: u32) {
+    let learner = Trainer::self_play(games);
+    match learner.save(LEARNER_SAVE_PATH) {
+        Ok(()) => println!("trained for {games} games, saved to {LEARNER_SAVE_PATH}"),
+        Err(err) => eprintln!("failed to save learner memory: {err}"),
+    }
+}
+
+/// Read `<program> [rows] [cols] [--load <path>] [--learner] [--train <games>]` from the command
+/// line. `rows`/`cols` fall back to [`DEFAULT_GRID_ROWS`]/[`DEFAULT_GRID_COLS`] when missing or
+/// unparsable, and are clamped to a size the window can lay out legibly; when `--load` succeeds
+/// `rows`/`cols` are ignored, since a loaded game already carries its own dimensions;
+/// `--learner` plays [`Player::Two`] with [`menace::Learner`] instead of [`ai::best_move`]; and
+/// `--train` skips the game entirely to bootstrap the learner's memory (see [`train`]).
+fn parse_args() -> Args {
+    let mut rows = None;
+    let mut cols = None;
+    let mut load_path = None;
+    let mut opponent = Opponent::Minimax;
+    let mut train_games = None;
+    let mut positional = 0;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--load" {
+            load_path = args.next();
+        } else if arg == "--learner" {
+            opponent = Opponent::Learner;
+        } else if arg == "--train" {
+            train_games = args.next().and_then(|n| n.parse().ok());
+        } else {
+            match positional {
+                0 => rows = arg.parse().ok(),
+                1 => cols = arg.parse().ok(),
+                _ => {}
+            }
+            positional += 1;
+        }
+    }
+
+    Args {
+        rows: rows
+            .unwrap_or(DEFAULT_GRID_ROWS)
+            .clamp(MIN_GRID_DIMENSION, MAX_GRID_DIMENSION),
+        cols: cols
+            .unwrap_or(DEFAULT_GRID_COLS)
+            .clamp(MIN_GRID_DIMENSION, MAX_GRID_DIMENSION),
+        load_path,
+        opponent,
+        train_games,
+    }
+}
+
+/// The live application state nannou drives: the game board, plus whichever opponent is playing
+/// [`Player::Two`] and (for the learner) the memory it's building up this run.
+struct Model {
+    state: GameState,
+    opponent: Opponent,
+    /// Only populated when `opponent` is [`Opponent::Learner`].
+    learner: Option<Learner>,
+    /// Moves the learner has played so far in the current game, in order. Reinforced and
+    /// cleared once the game ends; see [`finish_learner_game`].
+    learner_played: Vec<(BoardKey, usize)>,
 }
 
 /// Nannou calls this function to initialize the application
 /// This returns the data model for the application which is the state we want to track throughout
-/// the lifetime of the application. In our case this data is the game state.
-fn model(app: &App) -> GameState {
-    // create a new window to draw to
+/// the lifetime of the application. In our case this data is the game state, the chosen
+/// opponent, and (for the learner) its matchbox memory.
+fn model(app: &App, args: Args) -> Model {
+    // create the game state first, resuming a saved game if one was requested, so the window can
+    // be sized to fit whichever grid we ended up with (a loaded save carries its own dimensions)
+    let state = match args.load_path {
+        Some(path) => GameState::load(&path).unwrap_or_else(|err| {
+            eprintln!("failed to load {path}: {err}, starting a new game instead");
+            GameState::new(args.rows, args.cols)
+        }),
+        None => GameState::new(args.rows, args.cols),
+    };
+
+    let (window_width, window_height) = GameState::window_size(state.rows, state.cols);
     let _window = app
         .new_window()
         .title(app.exe_name().unwrap())
-        .size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .size(window_width, window_height)
         // on draw call the `view` function
         .view(view)
         // on any event (i.e. `MousePressed`) call the `event` function
@@ -33,97 +180,355 @@ fn model(app: &App) -> GameState {
         .build()
         .unwrap();
 
-    // create the data model for the app
-    GameState::new()
+    let learner = match args.opponent {
+        Opponent::Learner => Some(Learner::load(LEARNER_SAVE_PATH).unwrap_or_else(|err| {
+            eprintln!("failed to load {LEARNER_SAVE_PATH}: {err}, starting from a blank memory");
+            Learner::new()
+        })),
+        Opponent::Minimax => None,
+    };
+
+    Model {
+        state,
+        opponent: args.opponent,
+        learner,
+        learner_played: Vec::new(),
+    }
 }
 
 /// The view function will be called to draw the current game state.
-fn view(app: &App, model: &GameState, frame: Frame) {
+fn view(app: &App, model: &Model, frame: Frame) {
+    let state = &model.state;
     let draw = app.draw();
 
     // clear the previous frame
     draw.background().color(WHITE);
 
-    for square in &model.squares {
+    for square in &state.squares {
         // draw the square background
         draw.rect()
             .color(square.color())
             .xy(square.rect.xy())
             .wh(square.rect.wh());
+    }
 
-        // draw the walls
-        let [left, top, right, bottom] = &square.walls;
-
-        draw.rect()
-            .color(left.color())
-            .xy(left.rect.xy())
-            .wh(left.rect.wh());
-
-        draw.rect()
-            .color(top.color())
-            .xy(top.rect.xy())
-            .wh(top.rect.wh());
+    // draw each edge exactly once, straight from the shared stores, special-casing the one
+    // under the cursor so players can see which wall they're about to claim
+    for (idx, edge) in state
+        .horizontal_edges
+        .iter()
+        .chain(&state.vertical_edges)
+        .enumerate()
+    {
+        let color = if state.hovered_edge == Some(idx) {
+            state.current_player.hover_color()
+        } else {
+            edge.color()
+        };
 
         draw.rect()
-            .color(right.color())
-            .xy(right.rect.xy())
-            .wh(right.rect.wh());
+            .color(color)
+            .xy(edge.rect.xy())
+            .wh(edge.rect.wh());
+    }
 
-        draw.rect()
-            .color(bottom.color())
-            .xy(bottom.rect.xy())
-            .wh(bottom.rect.wh());
+    // HUD: running score for both players, and whose turn it is (or who won).
+    let (window_width, window_height) = GameState::window_size(state.rows, state.cols);
+    let hud_y = window_height as f32 / 2.0 - GRID_MARGIN / 2.0;
+    draw.text(&format!("{}", state.box_count(Player::One)))
+        .xy(pt2(-window_width as f32 / 4.0, hud_y))
+        .color(Player::One.color())
+        .font_size(HUD_FONT_SIZE);
+    draw.text(&format!("{}", state.box_count(Player::Two)))
+        .xy(pt2(window_width as f32 / 4.0, hud_y))
+        .color(Player::Two.color())
+        .font_size(HUD_FONT_SIZE);
+
+    match state.phase {
+        Phase::Playing => {
+            draw.text(&format!("{}'s turn", state.current_player.label()))
+                .xy(pt2(0.0, hud_y))
+                .color(state.current_player.color())
+                .font_size(HUD_FONT_SIZE);
+        }
+        Phase::Finished => {
+            let banner = match state
+                .box_count(Player::One)
+                .cmp(&state.box_count(Player::Two))
+            {
+                std::cmp::Ordering::Greater => "Player One wins! (space to play again)",
+                std::cmp::Ordering::Less => "Player Two wins! (space to play again)",
+                std::cmp::Ordering::Equal => "Draw! (space to play again)",
+            };
+            draw.text(banner)
+                .xy(pt2(0.0, hud_y))
+                .color(BLACK)
+                .font_size(HUD_FONT_SIZE);
+        }
     }
 
     draw.to_frame(app, &frame).unwrap();
 }
 
 // Update the game state based on an event that happened to the application window.
-fn event(app: &App, model: &mut GameState, event: WindowEvent) {
-    if matches!(event, WindowEvent::MousePressed(_)) {
+// `Player::One` is the human, `Player::Two` is played by `model.opponent`.
+fn event(app: &App, model: &mut Model, event: WindowEvent) {
+    if matches!(event, WindowEvent::MousePressed(_)) && model.state.current_player == Player::One {
         let point = Point2::new(app.mouse.x, app.mouse.y);
-        println!("click at {point:?}");
-
-        // TODO: implement the game logic
-        // - check if the click hit any walls
-        //   - if so mark the wall as taken by the player
-        // - check if any squares where completed by the player
-        //   - if so mark the squares as taken by the player
-        // - if the player took a wall its the next players turn
-        // - if the player took a square they get another turn
-        // - if all the squares are taken the game has ended, the player with the most squares wins
-        //
-        // Bonus ideas:
-        // - show which players turn it is
-        // - show which player won at the end
-        // - make the game prettier
-
-        model.current_player = match model.current_player {
-            Player::One => Player::Two,
-            Player::Two => Player::One,
+
+        if let Some(idx) = model.state.edge_at(point) {
+            if let Some(completed) = model.state.claim_edge(idx, Player::One) {
+                // completing a square grants another turn, otherwise play passes
+                if completed == 0 {
+                    model.state.current_player = Player::Two;
+                }
+
+                play_ai_turns(model);
+
+                // edges claimed by this click or the AI's reply are no longer hover candidates
+                model.state.hovered_edge = model
+                    .state
+                    .hovered_edge
+                    .filter(|&idx| model.state.edge(idx).taken_by.is_none());
+            }
+        }
+    }
+
+    if let WindowEvent::MouseMoved(point) = event {
+        model.state.hovered_edge = model
+            .state
+            .edge_at(point)
+            .filter(|&idx| model.state.edge(idx).taken_by.is_none());
+    }
+
+    if let WindowEvent::KeyPressed(Key::S) = event {
+        if let Err(err) = model.state.save(SAVE_PATH) {
+            eprintln!("failed to save game: {err}");
+        } else {
+            println!("game saved to {SAVE_PATH}");
+        }
+    }
+
+    if let WindowEvent::KeyPressed(Key::Space) = event {
+        if model.state.phase == Phase::Finished {
+            model.state = GameState::new(model.state.rows, model.state.cols);
+            model.learner_played.clear();
+        }
+    }
+}
+
+/// Let [`Player::Two`] keep claiming edges until the turn passes back to the human or the game
+/// ends. Needed because completing a box grants another turn, so one human click can trigger a
+/// whole sequence of moves. Which move is chosen, and whether it's recorded for reinforcement,
+/// depends on `model.opponent`.
+fn play_ai_turns(model: &mut Model) {
+    while model.state.current_player == Player::Two && !model.state.is_finished() {
+        let idx = match (model.opponent, &mut model.learner) {
+            (Opponent::Learner, Some(learner)) => {
+                let key = BoardKey::for_state(&model.state);
+                let chosen = learner.choose_move(&model.state);
+                model.learner_played.push((key, chosen));
+                chosen
+            }
+            _ => ai::best_move(&model.state, AI_SEARCH_DEPTH),
         };
+
+        let completed = model
+            .state
+            .claim_edge(idx, Player::Two)
+            .expect("the chosen move always returns an untaken edge");
+
+        if completed == 0 {
+            model.state.current_player = Player::One;
+        }
+    }
+
+    if model.state.is_finished() {
+        finish_learner_game(model);
+    }
+}
+
+/// Once a game the learner took part in has ended, reinforce it on the result via
+/// [`menace::Learner::reinforce`] and persist the updated memory to [`LEARNER_SAVE_PATH`], so the
+/// opponent is measurably stronger the next time it's loaded.
+fn finish_learner_game(model: &mut Model) {
+    let Some(learner) = &mut model.learner else {
+        return;
+    };
+    if model.learner_played.is_empty() {
+        return;
+    }
+
+    let outcome = match model
+        .state
+        .box_count(Player::Two)
+        .cmp(&model.state.box_count(Player::One))
+    {
+        std::cmp::Ordering::Greater => Outcome::Win,
+        std::cmp::Ordering::Less => Outcome::Loss,
+        std::cmp::Ordering::Equal => Outcome::Draw,
+    };
+
+    learner.reinforce(&model.learner_played, outcome);
+    model.learner_played.clear();
+
+    if let Err(err) = learner.save(LEARNER_SAVE_PATH) {
+        eprintln!("failed to save learner memory: {err}");
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 struct GameState {
     /// All squares in the game.
     squares: Vec<Square>,
+    /// Horizontal edges (the top/bottom walls of squares), indexed by `row * cols + col`
+    /// where `row` ranges over `0..=rows`.
+    horizontal_edges: Vec<Edge>,
+    /// Vertical edges (the left/right walls of squares), indexed by `row * (cols + 1) + col`
+    /// where `col` ranges over `0..=cols`.
+    vertical_edges: Vec<Edge>,
     /// The player whose turn it is.
     current_player: Player,
+    /// Number of rows of squares in the grid.
+    rows: usize,
+    /// Number of columns of squares in the grid.
+    cols: usize,
+    /// Combined index of the untaken edge currently under the cursor, if any. Transient UI
+    /// state, so it isn't part of a saved game.
+    #[serde(skip)]
+    hovered_edge: Option<usize>,
+    /// Whether the game is still being played or has been won. Defaults to `Playing` so save
+    /// files written before this field existed still load.
+    #[serde(default)]
+    phase: Phase,
+}
+
+/// Whether a [`GameState`] is still being played or has ended.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Phase {
+    #[default]
+    Playing,
+    Finished,
+}
+
+/// The geometry for a `rows` by `cols` grid, computed independently of any [`GameState`] so it
+/// can be reused both when building a fresh game and when recomputing geometry for one loaded
+/// from disk.
+struct Layout {
+    horizontal_rects: Vec<Rect>,
+    vertical_rects: Vec<Rect>,
+    square_rects: Vec<Rect>,
 }
 
 impl GameState {
-    /// Create a new game state.
-    fn new() -> Self {
+    /// Create a new game state for a `rows` by `cols` grid, its squares sized at
+    /// [`SQUARE_SIZE`] and centered on the origin; [`GameState::window_size`] computes the
+    /// window needed to fit it.
+    fn new(rows: usize, cols: usize) -> Self {
+        let layout = Self::layout(rows, cols);
+
+        // build every physical edge exactly once, before the squares that reference them
+        let horizontal_edges = layout
+            .horizontal_rects
+            .into_iter()
+            .map(Edge::from_rect)
+            .collect();
+        let vertical_edges: Vec<Edge> = layout
+            .vertical_rects
+            .into_iter()
+            .map(Edge::from_rect)
+            .collect();
+
+        // vertical edge indices follow horizontal ones in the combined index space
+        let horizontal_count = (rows + 1) * cols;
+
+        let squares = layout
+            .square_rects
+            .into_iter()
+            .enumerate()
+            .map(|(i, rect)| {
+                let row = i / cols;
+                let col = i % cols;
+
+                let top = row * cols + col;
+                let bottom = (row + 1) * cols + col;
+                let left = horizontal_count + row * (cols + 1) + col;
+                let right = horizontal_count + row * (cols + 1) + col + 1;
+
+                Square {
+                    rect,
+                    edges: [left, top, right, bottom],
+                    taken_by: None,
+                }
+            })
+            .collect();
+
+        Self {
+            squares,
+            horizontal_edges,
+            vertical_edges,
+            current_player: Player::One,
+            rows,
+            cols,
+            hovered_edge: None,
+            phase: Phase::Playing,
+        }
+    }
+
+    /// Save this game to `path` as a human-readable JSON5 document, so it can be resumed later
+    /// or shared as a board layout. Only the logical state is written; `Rect`s aren't
+    /// serde-friendly, and geometry is always rebuilt from `rows`/`cols` on load anyway.
+    fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let document =
+            json5::to_string(self).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        fs::write(path, document)
+    }
+
+    /// Load a game previously written by [`GameState::save`], recomputing all geometry from the
+    /// saved `rows`/`cols`.
+    fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let mut state: Self = json5::from_str(&contents)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        state.relayout();
+        Ok(state)
+    }
+
+    /// Recompute every square's and edge's [`Rect`] from `rows`/`cols`. Geometry isn't
+    /// serialized, so this has to run once after loading a saved game.
+    fn relayout(&mut self) {
+        let layout = Self::layout(self.rows, self.cols);
+
+        for (edge, rect) in self
+            .horizontal_edges
+            .iter_mut()
+            .zip(layout.horizontal_rects)
+        {
+            edge.rect = rect;
+        }
+        for (edge, rect) in self.vertical_edges.iter_mut().zip(layout.vertical_rects) {
+            edge.rect = rect;
+        }
+        for (square, rect) in self.squares.iter_mut().zip(layout.square_rects) {
+            square.rect = rect;
+        }
+    }
+
+    /// Compute the geometry for a `rows` by `cols` grid, centered on the origin with each square
+    /// sized at [`SQUARE_SIZE`].
+    fn layout(rows: usize, cols: usize) -> Layout {
+        let square_size = SQUARE_SIZE;
+        let wall_width = square_size * WALL_WIDTH_RATIO;
+
         // compute the size of the full grid
-        let grid_width = SQUARE_SIZE * GRID_ROWS as f32;
-        let grid_height = SQUARE_SIZE * GRID_COLS as f32;
+        let grid_width = square_size * cols as f32;
+        let grid_height = square_size * rows as f32;
 
         // compute the middle point of grid
         let grid_half_width = grid_width / 2.0;
         let grid_half_height = grid_height / 2.0;
 
-        let half_square_size = SQUARE_SIZE / 2.0;
+        let half_square_size = square_size / 2.0;
 
         // compute offset from the center to the top left square
         let grid_offset = Point2::new(
@@ -131,39 +536,172 @@ impl GameState {
             grid_half_height - half_square_size,
         );
 
-        // create a rectangle in the center of the screen and shift it to the top left of the grid
-        let mut current_row = Rect::from_w_h(SQUARE_SIZE, SQUARE_SIZE).shift(grid_offset);
+        let mut horizontal_rects = Vec::with_capacity((rows + 1) * cols);
+        for row in 0..=rows {
+            for col in 0..cols {
+                let x = grid_offset.x + col as f32 * square_size;
+                let y = grid_offset.y + half_square_size - row as f32 * square_size;
+                horizontal_rects.push(Rect::from_x_y_w_h(x, y, square_size, wall_width));
+            }
+        }
 
-        let mut squares = vec![];
+        let mut vertical_rects = Vec::with_capacity(rows * (cols + 1));
+        for row in 0..rows {
+            for col in 0..=cols {
+                let x = grid_offset.x - half_square_size + col as f32 * square_size;
+                let y = grid_offset.y - row as f32 * square_size;
+                vertical_rects.push(Rect::from_x_y_w_h(x, y, wall_width, square_size));
+            }
+        }
 
-        // start creating the squares for a row
-        for _ in 0..GRID_ROWS {
-            // Explicit clone to make sure we don't accidentally modify `current_row`
-            #[allow(clippy::clone_on_copy)]
-            let mut current_col = current_row.clone();
+        let mut square_rects = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let x = grid_offset.x + col as f32 * square_size;
+                let y = grid_offset.y - row as f32 * square_size;
+                square_rects.push(Rect::from_x_y_w_h(x, y, square_size, square_size));
+            }
+        }
 
-            // for each column in the grid create a square and move the `current_col` to its right
-            // note that the sides of the squares are touching (and therefore their walls overlap)
-            for _ in 0..GRID_COLS {
-                let square = Square::from_rect(current_col);
+        Layout {
+            horizontal_rects,
+            vertical_rects,
+            square_rects,
+        }
+    }
 
-                squares.push(square);
+    /// The window size needed to fit a `rows` by `cols` grid of [`SQUARE_SIZE`] squares, with
+    /// [`GRID_MARGIN`] left around it on every side. The window is laid out dynamically from the
+    /// grid dimensions, rather than the board being rescaled to fit a fixed window.
+    fn window_size(rows: usize, cols: usize) -> (u32, u32) {
+        let width = cols as f32 * SQUARE_SIZE + GRID_MARGIN * 2.0;
+        let height = rows as f32 * SQUARE_SIZE + GRID_MARGIN * 2.0;
+        (width.round() as u32, height.round() as u32)
+    }
 
-                current_col = current_col.right_of(current_col);
-            }
+    /// Look up an edge by its combined index, spanning both the horizontal and vertical stores.
+    fn edge(&self, idx: usize) -> &Edge {
+        match self.horizontal_edges.get(idx) {
+            Some(edge) => edge,
+            None => &self.vertical_edges[idx - self.horizontal_edges.len()],
+        }
+    }
 
-            // move to the next row
-            current_row = current_row.below(current_row);
+    /// Mutably look up an edge by its combined index.
+    fn edge_mut(&mut self, idx: usize) -> &mut Edge {
+        let horizontal_count = self.horizontal_edges.len();
+        if idx < horizontal_count {
+            &mut self.horizontal_edges[idx]
+        } else {
+            &mut self.vertical_edges[idx - horizontal_count]
         }
+    }
 
-        Self {
-            squares,
-            current_player: Player::One,
+    /// Hit-test a click against every edge's bounding box (with a little tolerance, since the
+    /// walls are thin) and return the combined index of the edge it landed on, if any.
+    fn edge_at(&self, point: Point2) -> Option<usize> {
+        let total = self.horizontal_edges.len() + self.vertical_edges.len();
+
+        (0..total).find(|&idx| {
+            let rect = self.edge(idx).rect;
+            let hit_box = Rect::from_x_y_w_h(
+                rect.x(),
+                rect.y(),
+                rect.w() + EDGE_CLICK_TOLERANCE * 2.0,
+                rect.h() + EDGE_CLICK_TOLERANCE * 2.0,
+            );
+            hit_box.contains(point)
+        })
+    }
+
+    /// Claim the edge at `idx` for `player`, then check the one or two squares bordering it for
+    /// completion. Returns [None] if the edge was already taken (in which case nothing changes),
+    /// otherwise the number of squares completed by this move (0, 1 or 2), which callers use to
+    /// decide whether the turn passes.
+    fn claim_edge(&mut self, idx: usize, player: Player) -> Option<usize> {
+        if self.edge(idx).taken_by.is_some() {
+            return None;
         }
+
+        self.edge_mut(idx).taken_by = Some(player);
+
+        let horizontal_edges = &self.horizontal_edges;
+        let vertical_edges = &self.vertical_edges;
+        let edge_taken = |edge_idx: usize| -> bool {
+            horizontal_edges
+                .get(edge_idx)
+                .unwrap_or_else(|| &vertical_edges[edge_idx - horizontal_edges.len()])
+                .taken_by
+                .is_some()
+        };
+
+        let mut completed = 0;
+        for square in &mut self.squares {
+            if square.taken_by.is_none()
+                && square.edges.contains(&idx)
+                && square.edges.iter().all(|&edge_idx| edge_taken(edge_idx))
+            {
+                square.taken_by = Some(player);
+                completed += 1;
+            }
+        }
+
+        if self.is_finished() {
+            self.phase = Phase::Finished;
+        }
+
+        Some(completed)
+    }
+
+    /// Combined indices of every edge that hasn't been claimed yet.
+    pub(crate) fn untaken_edges(&self) -> Vec<usize> {
+        let total = self.horizontal_edges.len() + self.vertical_edges.len();
+        (0..total)
+            .filter(|&idx| self.edge(idx).taken_by.is_none())
+            .collect()
+    }
+
+    /// Whether each edge (by combined index) has been claimed yet. Used to build a canonical,
+    /// position-only encoding of the board, e.g. for [`menace::BoardKey`](crate::menace::BoardKey).
+    pub(crate) fn edges_taken(&self) -> Vec<bool> {
+        let total = self.horizontal_edges.len() + self.vertical_edges.len();
+        (0..total)
+            .map(|idx| self.edge(idx).taken_by.is_some())
+            .collect()
+    }
+
+    /// How many edges of this square have been claimed so far.
+    pub(crate) fn square_edges_taken(&self, square: &Square) -> usize {
+        square
+            .edges
+            .iter()
+            .filter(|&&idx| self.edge(idx).taken_by.is_some())
+            .count()
+    }
+
+    /// Whether claiming `idx` would complete at least one of the squares bordering it.
+    pub(crate) fn completes_a_square(&self, idx: usize) -> bool {
+        self.squares
+            .iter()
+            .filter(|square| square.taken_by.is_none() && square.edges.contains(&idx))
+            .any(|square| self.square_edges_taken(square) == 3)
+    }
+
+    /// Number of squares already won by `player`.
+    pub(crate) fn box_count(&self, player: Player) -> usize {
+        self.squares
+            .iter()
+            .filter(|square| square.taken_by == Some(player))
+            .count()
+    }
+
+    /// The game is over once every square has been claimed.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.squares.iter().all(|square| square.taken_by.is_some())
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 enum Player {
     One,
     Two,
@@ -177,28 +715,67 @@ impl Player {
             Player::Two => GREEN,
         }
     }
+
+    /// A lighter tint of [`Player::color`], used to preview the edge a player is about to claim.
+    fn hover_color(&self) -> Rgb<u8> {
+        let color = self.color();
+        let lighten = |channel: u8| channel + (255 - channel) / 2;
+        Rgb::new(
+            lighten(color.red),
+            lighten(color.green),
+            lighten(color.blue),
+        )
+    }
+
+    /// A human-readable label for this player, used in the turn indicator.
+    fn label(&self) -> &'static str {
+        match self {
+            Player::One => "Player One",
+            Player::Two => "Player Two",
+        }
+    }
+
+    /// The other player.
+    pub(crate) fn other(&self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 struct Square {
-    /// The bounding box for the square, the walls are drawn on the edges of this [Rect].
+    /// The bounding box for the square, drawn as the square's background. Not serialized since
+    /// [`Rect`] isn't serde-friendly; [`GameState::relayout`] rebuilds it after loading.
     /// See: https://docs.rs/nannou/latest/nannou/geom/struct.Rect.html
+    #[serde(skip, default = "default_rect")]
     pub rect: Rect,
-    /// All 4 walls for the square in the order; left, top, right, bottom.
-    pub walls: [Wall; 4],
+    /// Combined indices into `GameState`'s edge stores, in the order; left, top, right, bottom.
+    pub edges: [usize; 4],
     /// If [None] the square has not been taken, else this contains the [Player] which has won the square.
     pub taken_by: Option<Player>,
 }
 
-struct Wall {
-    /// The bounding box for the wall.
+#[derive(Clone, Serialize, Deserialize)]
+struct Edge {
+    /// The bounding box for the edge. Not serialized since [`Rect`] isn't serde-friendly;
+    /// [`GameState::relayout`] rebuilds it after loading.
     /// See: https://docs.rs/nannou/latest/nannou/geom/struct.Rect.html
+    #[serde(skip, default = "default_rect")]
     pub rect: Rect,
-    /// If [None] the wall has not been taken, else this contains the [Player] which has taken the wall.
+    /// If [None] the edge has not been taken, else this contains the [Player] which has taken it.
     pub taken_by: Option<Player>,
 }
 
-impl Wall {
-    /// Create a new wall from the given [Rect].
+/// Placeholder used for `rect` fields while deserializing; always overwritten by
+/// [`GameState::relayout`] before the loaded game is used.
+fn default_rect() -> Rect {
+    Rect::from_x_y_w_h(0.0, 0.0, 0.0, 0.0)
+}
+
+impl Edge {
+    /// Create a new [Edge] from a [Rect].
     fn from_rect(rect: Rect) -> Self {
         Self {
             rect,
@@ -206,35 +783,13 @@ impl Wall {
         }
     }
 
-    /// Create a new [Wall] from the given x, y, width and height.
-    fn from_x_y_w_h(x: f32, y: f32, w: f32, h: f32) -> Self {
-        Self::from_rect(Rect::from_x_y_w_h(x, y, w, h))
-    }
-
-    /// Get the current color for this wall.
+    /// Get the current color for this edge.
     fn color(&self) -> Rgb<u8> {
         self.taken_by.map(|player| player.color()).unwrap_or(BLACK)
     }
 }
 
 impl Square {
-    /// Create a new square from the given [Rect].
-    fn from_rect(rect: Rect) -> Self {
-        let left = Wall::from_x_y_w_h(rect.left(), rect.y(), WALL_WIDTH, rect.h());
-
-        let top = Wall::from_x_y_w_h(rect.x(), rect.top(), rect.w(), WALL_WIDTH);
-
-        let right = Wall::from_x_y_w_h(rect.right(), rect.y(), WALL_WIDTH, rect.h());
-
-        let bottom = Wall::from_x_y_w_h(rect.x(), rect.bottom(), rect.w(), WALL_WIDTH);
-
-        Self {
-            rect,
-            walls: [left, top, right, bottom],
-            taken_by: None,
-        }
-    }
-
     /// Get the current color for this square.
     fn color(&self) -> Rgb<u8> {
         self.taken_by.map(|player| player.color()).unwrap_or(WHITE)
@@ -0,0 +1,285 @@
+//! An alternative opponent for [`crate::Player::Two`], modeled after Donald Michie's MENACE
+//! (Matchbox Educable Noughts And Crosses Engine): instead of searching, it learns purely from
+//! win/loss feedback.
+//!
+//! Every board position it has seen maps to the legal moves available there, each with a "bead
+//! count". [`Learner::choose_move`] picks a move with probability proportional to its beads;
+//! [`Learner::reinforce`] is called once a game ends and adjusts the beads for every move the
+//! learner played that game, so it gets a little stronger each time it plays.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::ai;
+use crate::{GameState, Player};
+
+/// How many beads a move gets the first time a position is seen.
+const INITIAL_BEADS: u32 = 4;
+
+/// A canonical encoding of a board position: which edges are taken, and whose turn it is.
+/// Deliberately ignores everything else: geometry, and who owns which square (the taken edges
+/// only imply which squares are complete, not their owners).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoardKey {
+    taken: Vec<bool>,
+    turn: Player,
+}
+
+impl BoardKey {
+    /// Encode the current position of `state`, including whose turn it is.
+    pub(crate) fn for_state(state: &GameState) -> Self {
+        Self {
+            taken: state.edges_taken(),
+            turn: state.current_player,
+        }
+    }
+
+    /// The edges that were still free at this position, i.e. the legal moves.
+    fn untaken_edges(&self) -> Vec<usize> {
+        self.taken
+            .iter()
+            .enumerate()
+            .filter(|&(_, &taken)| !taken)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+/// A position's legal moves, each with its current bead count.
+type Moves = Vec<(usize, u32)>;
+
+/// Whether the learner won, lost, or drew the game it's being reinforced for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// The learner's matchbox memory: every position encountered so far, and the bead counts it has
+/// learned for the moves available there.
+#[derive(Default)]
+pub struct Learner {
+    table: HashMap<BoardKey, Moves>,
+}
+
+impl Learner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a previously saved table from `path`, or start with an empty one if it doesn't exist
+    /// yet.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        let table = contents.lines().filter_map(parse_line).collect();
+        Ok(Self { table })
+    }
+
+    /// Save the table to `path`, one position per line.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents: String = self
+            .table
+            .iter()
+            .map(|(key, moves)| format_line(key, moves) + "\n")
+            .collect();
+        fs::write(path, contents)
+    }
+
+    /// Pick a move for `state`, with probability proportional to each move's bead count. The
+    /// first time a position is seen, every untaken edge starts out with [`INITIAL_BEADS`].
+    pub fn choose_move(&mut self, state: &GameState) -> usize {
+        let key = BoardKey::for_state(state);
+        let moves = self.table.entry(key).or_insert_with_key(|key| {
+            key.untaken_edges()
+                .into_iter()
+                .map(|idx| (idx, INITIAL_BEADS))
+                .collect()
+        });
+
+        weighted_pick(moves)
+    }
+
+    /// Reinforce every move the learner played this game, most recent first doesn't matter:
+    /// add a bead to each on a win, remove one on a loss (deleting the move entirely once its last
+    /// bead is gone, to represent "never do that again", and repopulating the position from
+    /// scratch if that empties it out), and leave counts untouched on a draw.
+    pub fn reinforce(&mut self, played: &[(BoardKey, usize)], outcome: Outcome) {
+        for (key, chosen) in played {
+            let Some(moves) = self.table.get_mut(key) else {
+                continue;
+            };
+
+            match outcome {
+                Outcome::Win => {
+                    if let Some(entry) = moves.iter_mut().find(|(idx, _)| idx == chosen) {
+                        entry.1 += 1;
+                    }
+                }
+                Outcome::Loss => {
+                    if let Some(pos) = moves.iter().position(|(idx, _)| idx == chosen) {
+                        if moves[pos].1 <= 1 {
+                            moves.remove(pos);
+                        } else {
+                            moves[pos].1 -= 1;
+                        }
+                    }
+
+                    if moves.is_empty() {
+                        moves.extend(
+                            key.untaken_edges()
+                                .into_iter()
+                                .map(|idx| (idx, INITIAL_BEADS)),
+                        );
+                    }
+                }
+                Outcome::Draw => {}
+            }
+        }
+    }
+}
+
+/// Pick an edge from `moves` with probability proportional to its bead count.
+fn weighted_pick(moves: &Moves) -> usize {
+    let total: u32 = moves.iter().map(|&(_, beads)| beads).sum();
+    if total == 0 {
+        return moves[0].0;
+    }
+
+    let mut remaining = (next_random() % total as u64) as u32;
+    for &(idx, beads) in moves {
+        if remaining < beads {
+            return idx;
+        }
+        remaining -= beads;
+    }
+
+    moves.last().unwrap().0
+}
+
+/// A tiny xorshift PRNG seeded from the system clock. Good enough for weighting move choices;
+/// no need to pull in a dependency for it.
+fn next_random() -> u64 {
+    use std::cell::Cell;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64 | 1,
+        );
+    }
+
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        x
+    })
+}
+
+fn parse_line(line: &str) -> Option<(BoardKey, Moves)> {
+    let (turn, rest) = line.split_once('|')?;
+    let (taken, moves) = rest.split_once('|')?;
+
+    let turn = match turn {
+        "1" => Player::One,
+        "2" => Player::Two,
+        _ => return None,
+    };
+    let taken: Vec<bool> = taken.chars().map(|c| c == '1').collect();
+
+    let moves: Moves = moves
+        .split(',')
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let (idx, beads) = entry.split_once(':')?;
+            Some((idx.parse().ok()?, beads.parse().ok()?))
+        })
+        .collect();
+
+    Some((BoardKey { taken, turn }, moves))
+}
+
+fn format_line(key: &BoardKey, moves: &Moves) -> String {
+    let turn = match key.turn {
+        Player::One => '1',
+        Player::Two => '2',
+    };
+    let taken: String = key
+        .taken
+        .iter()
+        .map(|&b| if b { '1' } else { '0' })
+        .collect();
+    let moves = moves
+        .iter()
+        .map(|(idx, beads)| format!("{idx}:{beads}"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("{turn}|{taken}|{moves}")
+}
+
+/// Bootstraps a [`Learner`] by playing it against [`ai::best_move`] and reinforcing after every
+/// game, so the matchbox memory has something to work with before a human ever plays it.
+pub struct Trainer;
+
+impl Trainer {
+    /// Search depth used for the minimax sparring partner during self-play.
+    const OPPONENT_DEPTH: u32 = 4;
+
+    /// Play `games` games of the learner (as [`Player::Two`]) against the minimax AI (as
+    /// [`Player::One`]), reinforcing the learner after each one.
+    pub fn self_play(games: u32) -> Learner {
+        let mut learner = Learner::new();
+        for _ in 0..games {
+            Self::play_one_game(&mut learner);
+        }
+        learner
+    }
+
+    fn play_one_game(learner: &mut Learner) {
+        let mut state = GameState::new(crate::DEFAULT_GRID_ROWS, crate::DEFAULT_GRID_COLS);
+        let mut played = Vec::new();
+
+        while !state.is_finished() {
+            let mover = state.current_player;
+
+            let idx = match mover {
+                Player::Two => {
+                    let key = BoardKey::for_state(&state);
+                    let chosen = learner.choose_move(&state);
+                    played.push((key, chosen));
+                    chosen
+                }
+                Player::One => ai::best_move(&state, Self::OPPONENT_DEPTH),
+            };
+
+            let completed = state
+                .claim_edge(idx, mover)
+                .expect("chosen move is always untaken");
+
+            if completed == 0 {
+                state.current_player = mover.other();
+            }
+        }
+
+        let learner_boxes = state.box_count(Player::Two);
+        let opponent_boxes = state.box_count(Player::One);
+        let outcome = match learner_boxes.cmp(&opponent_boxes) {
+            std::cmp::Ordering::Greater => Outcome::Win,
+            std::cmp::Ordering::Less => Outcome::Loss,
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        };
+
+        learner.reinforce(&played, outcome);
+    }
+}